@@ -1,10 +1,14 @@
-use clap::{CommandFactory, Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
 use clap_complete::{generate, Shell};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use once_cell::sync::Lazy;
+use rayon::prelude::*;
+use regex::Regex;
+use serde::Serialize;
 use std::collections::HashMap;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::Read;
 use std::path::{Path, PathBuf};
-use walkdir::{DirEntry, WalkDir};
 
 /// ファイル階層と責務をツリー状に表示するCLIツール
 #[derive(Parser)]
@@ -23,6 +27,70 @@ struct Cli {
     /// 調査するディレクトリのパス
     #[arg(default_value = ".", index = 1)]
     path: String,
+
+    /// 各ディレクトリの累積サイズを人間可読な形式で表示する
+    #[arg(short = 's', long = "size")]
+    size: bool,
+
+    /// ツリー表示後に、サイズが大きいディレクトリ上位 N 件を一覧表示する
+    #[arg(long = "top", value_name = "N")]
+    top: Option<usize>,
+
+    /// 各行に表示するパスの形式
+    #[arg(long = "path-format", value_enum, default_value_t = PathFormat::Name)]
+    path_format: PathFormat,
+
+    /// 拡張子→コメント記号の独自対応表を読み込む
+    #[arg(long = "comment-map", value_name = "FILE")]
+    comment_map: Option<PathBuf>,
+
+    /// ファイルを一切開かず、名前だけを高速に表示する
+    #[arg(long = "names-only")]
+    names_only: bool,
+
+    /// 出力形式
+    #[arg(long = "format", value_enum, default_value_t = OutputFormat::Tree)]
+    format: OutputFormat,
+
+    /// 除外するパターン (グロブ、繰り返し指定可)。一致した要素は配下ごと除外される
+    #[arg(long = "exclude", value_name = "GLOB")]
+    exclude: Vec<String>,
+
+    /// ファイル名がいずれかのパターンに一致するものだけを表示する (グロブ)
+    #[arg(long = "include", value_name = "GLOB")]
+    include: Vec<String>,
+
+    /// 走査するルートからの最大深さ
+    #[arg(long = "max-depth", value_name = "N")]
+    max_depth: Option<usize>,
+
+    /// ドットで始まる隠しファイルも表示する
+    #[arg(long = "show-hidden")]
+    show_hidden: bool,
+}
+
+/// 走査結果の出力形式。
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// ASCII ツリー (既定)
+    Tree,
+    /// JSON
+    Json,
+    /// YAML
+    Yaml,
+}
+
+/// 各エントリ行に表示するパスの形式。
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum PathFormat {
+    /// ファイル名のみ (既定)
+    Name,
+    /// 調査対象ルートからの相対パス
+    Relative,
+    /// プロセスのカレントディレクトリからの相対パス
+    CwdRelative,
+    /// 絶対パス
+    Absolute,
 }
 
 #[derive(Subcommand)]
@@ -35,6 +103,161 @@ enum Commands {
     },
 }
 
+/// ツリーの1ノード。ディレクトリなら `children` を持ち、ファイルなら
+/// 先頭コメントから抽出した責務を `responsibility` に保持する。
+struct Node {
+    name: String,
+    is_dir: bool,
+    children: Vec<Node>,
+    responsibility: Option<String>,
+    /// ディレクトリなら配下ファイルの累積バイト数、ファイルなら自身のサイズ
+    size: u64,
+    /// このノードの絶対パス (パス形式の表示に使用)
+    path: PathBuf,
+}
+
+/// 走査時に子要素を間引くためのフィルタ群。
+struct Filters {
+    exclude: Option<GlobSet>,
+    include: Option<GlobSet>,
+    max_depth: Option<usize>,
+    show_hidden: bool,
+}
+
+impl Filters {
+    /// `path` を残すかどうかを判定する (深さの制限は `build_node` 側で扱う)。
+    /// ディレクトリは `--include` の対象外とし、配下の一致ファイルへ到達できる
+    /// ように常に辿れるようにする。
+    fn keep(&self, path: &Path, is_dir: bool) -> bool {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+
+        if !self.show_hidden && name.starts_with('.') {
+            return false;
+        }
+        if let Some(set) = &self.exclude {
+            if set.is_match(name) {
+                return false;
+            }
+        }
+        if let Some(set) = &self.include {
+            if !is_dir && !set.is_match(name) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// グロブ文字列の集合から `GlobSet` を構築する。空なら `None`。
+/// 指定されたパターンがすべて不正な場合は、何にも一致しない空集合を黙って
+/// 返す代わりにエラーとして終了する。
+fn build_globset(globs: &[String]) -> Option<GlobSet> {
+    if globs.is_empty() {
+        return None;
+    }
+    let mut builder = GlobSetBuilder::new();
+    let mut valid = 0;
+    for g in globs {
+        match Glob::new(g) {
+            Ok(glob) => {
+                builder.add(glob);
+                valid += 1;
+            }
+            Err(e) => eprintln!("Error: Invalid glob '{}': {}", g, e),
+        }
+    }
+    if valid == 0 {
+        eprintln!("Error: No valid glob patterns were provided");
+        std::process::exit(1);
+    }
+    match builder.build() {
+        Ok(set) => Some(set),
+        Err(e) => {
+            eprintln!("Error: Could not build glob set: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// シリアライズ用のノード表現。`name` / `type` / `responsibility` と
+/// 入れ子の `children` のみを公開し、内部専用のサイズやパスは含めない。
+#[derive(Serialize)]
+struct NodeOut {
+    name: String,
+    #[serde(rename = "type")]
+    node_type: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    responsibility: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    children: Vec<NodeOut>,
+}
+
+impl NodeOut {
+    fn from_node(node: &Node) -> Self {
+        NodeOut {
+            name: node.name.clone(),
+            node_type: if node.is_dir { "dir" } else { "file" },
+            responsibility: node.responsibility.clone(),
+            children: node.children.iter().map(NodeOut::from_node).collect(),
+        }
+    }
+}
+
+/// ツリー描画時の表示オプションをまとめたもの。
+struct Render<'a> {
+    show_size: bool,
+    path_format: PathFormat,
+    root: &'a Path,
+    cwd: Option<PathBuf>,
+}
+
+impl Render<'_> {
+    /// パス形式に従ってノードの表示名を組み立てる。
+    fn label(&self, node: &Node) -> String {
+        match self.path_format {
+            PathFormat::Name => node.name.clone(),
+            PathFormat::Relative => node
+                .path
+                .strip_prefix(self.root)
+                .map(|p| p.to_string_lossy().into_owned())
+                .unwrap_or_else(|_| node.name.clone()),
+            PathFormat::Absolute => node.path.to_string_lossy().into_owned(),
+            PathFormat::CwdRelative => match &self.cwd {
+                Some(cwd) => cwd_relative(&node.path, cwd).to_string_lossy().into_owned(),
+                None => node.path.to_string_lossy().into_owned(),
+            },
+        }
+    }
+}
+
+/// `cwd` から `target` への相対パスを求める。共通の祖先まで遡って `..` を
+/// 並べ、残りを連結する。共通の接頭辞が無い場合は絶対パスを返す。
+fn cwd_relative(target: &Path, cwd: &Path) -> PathBuf {
+    let common: PathBuf = target
+        .components()
+        .zip(cwd.components())
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a.as_os_str())
+        .collect();
+
+    if common.as_os_str().is_empty() {
+        return target.to_path_buf();
+    }
+
+    let ups = cwd.strip_prefix(&common).map(|p| p.components().count()).unwrap_or(0);
+    let down = target.strip_prefix(&common).unwrap_or(target);
+
+    let mut rel = PathBuf::new();
+    for _ in 0..ups {
+        rel.push("..");
+    }
+    rel.push(down);
+    if rel.as_os_str().is_empty() {
+        rel.push(".");
+    }
+    rel
+}
+
 fn main() {
     let cli = Cli::parse();
 
@@ -54,102 +277,381 @@ fn main() {
         }
     };
 
-    let entries = collect_entries(&target_dir);
-    print_tree(&entries, &target_dir, &mut Vec::new());
+    let db = CommentDb::load(cli.comment_map.as_deref());
+    let filters = Filters {
+        exclude: build_globset(&cli.exclude),
+        include: build_globset(&cli.include),
+        max_depth: cli.max_depth,
+        show_hidden: cli.show_hidden,
+    };
+    let root = build_node(&target_dir, &db, cli.names_only, &filters, 0);
+
+    // --size / --top はツリー表示専用。構造化出力では黙って無視せず警告する
+    if cli.format != OutputFormat::Tree && (cli.size || cli.top.is_some()) {
+        eprintln!("Warning: --size and --top are ignored with --format json/yaml");
+    }
+
+    match cli.format {
+        OutputFormat::Json => {
+            let out = NodeOut::from_node(&root);
+            println!("{}", serde_json::to_string_pretty(&out).unwrap());
+        }
+        OutputFormat::Yaml => {
+            let out = NodeOut::from_node(&root);
+            print!("{}", serde_yaml_ng::to_string(&out).unwrap());
+        }
+        OutputFormat::Tree => {
+            let render = Render {
+                show_size: cli.size,
+                path_format: cli.path_format,
+                root: &target_dir,
+                cwd: std::env::current_dir()
+                    .ok()
+                    .and_then(|c| c.canonicalize().ok()),
+            };
+            print_tree(&root, &mut Vec::new(), &render);
+
+            if let Some(n) = cli.top {
+                print_top_dirs(&root, n);
+            }
+        }
+    }
+}
+
+/// バイト数を `1.2K` / `3.4M` のような人間可読な文字列に変換する。
+fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 6] = ["B", "K", "M", "G", "T", "P"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{}{}", bytes, UNITS[unit])
+    } else {
+        format!("{:.1}{}", value, UNITS[unit])
+    }
 }
 
-fn collect_entries(target_dir: &Path) -> HashMap<PathBuf, Vec<DirEntry>> {
-    let mut entries: HashMap<PathBuf, Vec<DirEntry>> = HashMap::new();
+/// 木を走査してディレクトリを累積サイズの降順に並べ、上位 N 件を表示する。
+fn print_top_dirs(root: &Node, n: usize) {
+    let mut dirs: Vec<(String, u64)> = Vec::new();
+    collect_dirs(root, &root.name, &mut dirs);
+    dirs.sort_by_key(|d| std::cmp::Reverse(d.1));
 
-    // ルートディレクトリをエントリに追加
-    entries.entry(target_dir.to_path_buf()).or_default();
+    println!();
+    println!("Top {} directories by size:", n);
+    for (path, size) in dirs.into_iter().take(n) {
+        println!("{:>8}  {}", human_size(size), path);
+    }
+}
 
-    for entry in WalkDir::new(target_dir)
-        .into_iter()
-        .filter_entry(|e| !is_hidden(e))
-        .filter_map(Result::ok)
-    {
-        let _path = entry.path().to_path_buf();
-        let parent = entry.path().parent().unwrap().to_path_buf();
-        entries.entry(parent).or_default().push(entry);
+fn collect_dirs(node: &Node, path: &str, out: &mut Vec<(String, u64)>) {
+    if !node.is_dir {
+        return;
     }
+    out.push((path.to_string(), node.size));
+    for child in &node.children {
+        if child.is_dir {
+            let child_path = format!("{}/{}", path, child.name);
+            collect_dirs(child, &child_path, out);
+        }
+    }
+}
+
+/// 指定パスを根として木構造を再帰的に構築する。各ディレクトリの子要素は
+/// rayon で並列に処理し、ファイルの責務読み取りを並行して行う。
+fn build_node(
+    path: &Path,
+    db: &CommentDb,
+    names_only: bool,
+    filters: &Filters,
+    depth: usize,
+) -> Node {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string_lossy().into_owned());
+
+    if path.is_dir() {
+        let child_depth = depth + 1;
+        // 最大深さを超える場合は read_dir に入らず子要素を切り捨てる。
+        // シンボリックリンクされたディレクトリは辿らず葉として扱う
+        // (循環リンクによる無限再帰を防ぐ。WalkDir 時代の挙動に合わせる)。
+        let within_depth = filters.max_depth.is_none_or(|m| child_depth <= m);
+        let follow = within_depth && !path.is_symlink();
+
+        let child_paths: Vec<PathBuf> = if follow {
+            match std::fs::read_dir(path) {
+                Ok(read_dir) => read_dir
+                    .filter_map(Result::ok)
+                    .map(|e| e.path())
+                    .filter(|p| filters.keep(p, p.is_dir()))
+                    .collect(),
+                Err(_) => Vec::new(),
+            }
+        } else {
+            Vec::new()
+        };
+
+        let mut children: Vec<Node> = child_paths
+            .par_iter()
+            .map(|p| build_node(p, db, names_only, filters, child_depth))
+            .collect();
 
-    // 各ディレクトリ内のエントリをソート
-    for vec in entries.values_mut() {
-        vec.sort_by(|a, b| {
-            a.file_type()
-                .is_dir()
-                .cmp(&b.file_type().is_dir())
+        // --include 指定時は、一致ファイルを含まない空のディレクトリを枝刈りする
+        // (「一致するものだけ表示」の意図に沿うよう骸骨だけの枝を残さない)。
+        if filters.include.is_some() {
+            children.retain(|c| !c.is_dir || !c.children.is_empty());
+        }
+
+        // ディレクトリ優先・大文字小文字を無視した名前順でソート
+        children.sort_by(|a, b| {
+            a.is_dir
+                .cmp(&b.is_dir)
                 .reverse()
-                .then_with(|| {
-                    let a_name = a.file_name().to_string_lossy().to_lowercase();
-                    let b_name = b.file_name().to_string_lossy().to_lowercase();
-                    a_name.cmp(&b_name)
-                })
+                .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
         });
-    }
 
-    entries
+        // 後順の畳み込み: 配下ノードのサイズを合計する
+        let size = children.iter().map(|c| c.size).sum();
+
+        Node {
+            name,
+            is_dir: true,
+            children,
+            responsibility: None,
+            size,
+            path: path.to_path_buf(),
+        }
+    } else {
+        // --names-only ではファイルを開かず責務の読み取りを完全に省く
+        let responsibility = if names_only {
+            None
+        } else {
+            Some(get_responsibility(path, db))
+        };
+        let size = path.metadata().map(|m| m.len()).unwrap_or(0);
+        Node {
+            name,
+            is_dir: false,
+            children: Vec::new(),
+            responsibility,
+            size,
+            path: path.to_path_buf(),
+        }
+    }
 }
 
-fn print_tree(entries: &HashMap<PathBuf, Vec<DirEntry>>, path: &Path, prefix: &mut Vec<bool>) {
-    if let Some(children) = entries.get(path) {
-        let count = children.len();
-        for (i, entry) in children.iter().enumerate() {
-            let is_last = i == count - 1;
-            let file_name = entry.file_name().to_string_lossy();
-            let mut line_prefix = String::new();
-            for &last in prefix.iter() {
-                if last {
-                    line_prefix.push_str("    ");
-                } else {
-                    line_prefix.push_str("│   ");
-                }
-            }
-            if is_last {
-                line_prefix.push_str("└── ");
+fn print_tree(node: &Node, prefix: &mut Vec<bool>, render: &Render) {
+    let count = node.children.len();
+    for (i, child) in node.children.iter().enumerate() {
+        let is_last = i == count - 1;
+        let mut line_prefix = String::new();
+        for &last in prefix.iter() {
+            if last {
+                line_prefix.push_str("    ");
             } else {
-                line_prefix.push_str("├── ");
+                line_prefix.push_str("│   ");
             }
+        }
+        if is_last {
+            line_prefix.push_str("└── ");
+        } else {
+            line_prefix.push_str("├── ");
+        }
 
-            if entry.path().is_dir() {
-                println!("{}{}", line_prefix, file_name);
-                prefix.push(is_last);
-                print_tree(entries, &entry.path(), prefix);
-                prefix.pop();
+        let label = render.label(child);
+        if child.is_dir {
+            if render.show_size {
+                println!("{}{} [{}]", line_prefix, label, human_size(child.size));
             } else {
-                let responsibility = get_responsibility(&entry.path());
-                println!("{}{} - {}", line_prefix, file_name, responsibility);
+                println!("{}{}", line_prefix, label);
             }
+            prefix.push(is_last);
+            print_tree(child, prefix, render);
+            prefix.pop();
+        } else if let Some(responsibility) = &child.responsibility {
+            println!("{}{} - {}", line_prefix, label, responsibility);
+        } else {
+            // --names-only では責務を付けずに名前のみ表示する
+            println!("{}{}", line_prefix, label);
         }
     }
 }
 
-fn is_hidden(entry: &DirEntry) -> bool {
-    entry
-        .file_name()
-        .to_str()
-        .map(|s| s.starts_with('.'))
-        .unwrap_or(false)
-}
-
-fn get_responsibility(path: &Path) -> String {
-    if let Ok(file) = File::open(path) {
-        let reader = BufReader::new(file);
-        for line in reader.lines() {
-            if let Ok(line) = line {
-                let trimmed_line = line.trim();
-                if !trimmed_line.is_empty() {
-                    if trimmed_line.starts_with("//") || trimmed_line.starts_with("#") {
-                        return trimmed_line
-                            .trim_start_matches(|c: char| c.is_whitespace() || c == '/' || c == '#')
-                            .to_string();
-                    } else {
-                        break;
-                    }
+/// ある言語のコメント記号と、そこから本文を取り出すために事前コンパイルした
+/// 正規表現をまとめたもの。
+struct CommentSyntax {
+    matchers: Vec<Regex>,
+}
+
+impl CommentSyntax {
+    /// 行コメントの接頭辞とブロックコメントの区切りから照合器を構築する。
+    fn new(line_prefixes: &[&str], block: Option<(&str, &str)>) -> Self {
+        let mut matchers = Vec::new();
+        for p in line_prefixes {
+            let esc = regex::escape(p);
+            // 接頭辞を丸ごと消費したうえで、末尾文字の繰り返し (`///`, `//!` の
+            // `/` や `##` の `#` 等) も取り込む。そうしないと `///` が `//` と
+            // 一致して本文に余分な `/` が残ってしまう。
+            let tail = p
+                .chars()
+                .last()
+                .map(|c| regex::escape(&c.to_string()))
+                .unwrap_or_default();
+            matchers.push(
+                Regex::new(&format!(r"^\s*(?:{}){}*\s*(.*?)\s*$", esc, tail)).unwrap(),
+            );
+        }
+        if let Some((open, close)) = block {
+            let eo = regex::escape(open);
+            let ec = regex::escape(close);
+            matchers
+                .push(Regex::new(&format!(r"^\s*{}\s*(.*?)(?:\s*{})?\s*$", eo, ec)).unwrap());
+        }
+        CommentSyntax { matchers }
+    }
+
+    /// 1行にいずれかの記号が一致すれば、区切りを除いた本文を返す。
+    fn extract(&self, line: &str) -> Option<String> {
+        for re in &self.matchers {
+            if let Some(caps) = re.captures(line) {
+                if let Some(body) = caps.get(1) {
+                    // 内部ドキュメント (`//!`) や javadoc 風 (`/** */`) の先頭に
+                    // 残る `!` / `*` を1つ取り除く。
+                    let body = body.as_str().trim();
+                    let body = body
+                        .strip_prefix(['!', '*'])
+                        .map(str::trim_start)
+                        .unwrap_or(body);
+                    return Some(body.trim().to_string());
                 }
             }
         }
+        None
+    }
+}
+
+/// 拡張子ごとの組み込みコメント規則。初回使用時に一度だけコンパイルされる。
+static BUILTIN: Lazy<HashMap<&'static str, CommentSyntax>> = Lazy::new(|| {
+    let mut m = HashMap::new();
+    let slash = || CommentSyntax::new(&["//"], Some(("/*", "*/")));
+    for ext in ["rs", "c", "h", "cpp", "js", "ts", "go", "java"] {
+        m.insert(ext, slash());
+    }
+    for ext in ["py", "sh", "bash", "rb", "yaml", "yml", "toml"] {
+        m.insert(ext, CommentSyntax::new(&["#"], None));
+    }
+    m.insert("html", CommentSyntax::new(&[], Some(("<!--", "-->"))));
+    m.insert("xml", CommentSyntax::new(&[], Some(("<!--", "-->"))));
+    m.insert("lua", CommentSyntax::new(&["--"], None));
+    m.insert("sql", CommentSyntax::new(&["--"], None));
+    m
+});
+
+/// 規則が無い拡張子に対する既定の記号 (従来どおり `//` と `#` を解釈する)。
+static DEFAULT_SYNTAX: Lazy<CommentSyntax> = Lazy::new(|| CommentSyntax::new(&["//", "#"], None));
+
+/// 組み込み規則とユーザー指定の `--comment-map` を束ねた対応表。
+struct CommentDb {
+    user: HashMap<String, CommentSyntax>,
+}
+
+impl CommentDb {
+    /// 必要なら `--comment-map` のファイルを読み込んで対応表を構築する。
+    fn load(comment_map: Option<&Path>) -> Self {
+        let user = comment_map.map(parse_comment_map).unwrap_or_default();
+        CommentDb { user }
+    }
+
+    /// 拡張子に対応する規則を返す。ユーザー指定 → 組み込み → 既定の順で探す。
+    fn syntax_for(&self, ext: &str) -> &CommentSyntax {
+        self.user
+            .get(ext)
+            .or_else(|| BUILTIN.get(ext))
+            .unwrap_or(&DEFAULT_SYNTAX)
+    }
+}
+
+/// `--comment-map` のファイルを解析する。各行は空白区切りで
+/// `<exts> <line_prefixes> [<block_open> <block_close>]` の形式を取り、
+/// `exts` と `line_prefixes` はカンマ区切り、記号が無い列は `-` で示す。
+/// `#` で始まる行と空行は無視する。
+fn parse_comment_map(path: &Path) -> HashMap<String, CommentSyntax> {
+    let mut map = HashMap::new();
+    let contents = match std::fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("Error: Could not read comment map: {}", e);
+            return map;
+        }
+    };
+
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let cols: Vec<&str> = line.split_whitespace().collect();
+        if cols.len() < 2 {
+            continue;
+        }
+
+        let exts: Vec<&str> = cols[0].split(',').filter(|s| !s.is_empty()).collect();
+        let prefixes: Vec<&str> = if cols[1] == "-" {
+            Vec::new()
+        } else {
+            cols[1].split(',').filter(|s| !s.is_empty()).collect()
+        };
+        let block = if cols.len() >= 4 && cols[2] != "-" && cols[3] != "-" {
+            Some((cols[2], cols[3]))
+        } else {
+            None
+        };
+
+        for ext in exts {
+            map.insert(ext.to_string(), CommentSyntax::new(&prefixes, block));
+        }
+    }
+    map
+}
+
+fn get_responsibility(path: &Path, db: &CommentDb) -> String {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("");
+    let syntax = db.syntax_for(ext);
+
+    // コメントは常に先頭付近にあるため、ファイル全体ではなく先頭の数 KB だけを
+    // 読み込む。
+    const SAMPLE_LEN: u64 = 8 * 1024;
+    let mut buf = Vec::new();
+    if File::open(path)
+        .and_then(|f| f.take(SAMPLE_LEN).read_to_end(&mut buf))
+        .is_err()
+    {
+        return "No responsibility comment".to_string();
+    }
+
+    // 読み込んだ先頭に NUL バイトがあればバイナリとみなす
+    if buf.contains(&0) {
+        return "binary".to_string();
+    }
+
+    let text = String::from_utf8_lossy(&buf);
+    for line in text.lines() {
+        let trimmed = line.trim();
+        // 空行とシェバングは読み飛ばす
+        if trimmed.is_empty() || trimmed.starts_with("#!") {
+            continue;
+        }
+        return match syntax.extract(trimmed) {
+            Some(body) => body,
+            None => "No responsibility comment".to_string(),
+        };
     }
     "No responsibility comment".to_string()
 }